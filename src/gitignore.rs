@@ -0,0 +1,84 @@
+//! A minimal `.gitignore` matcher for filtering `write-tree`'s directory walk.
+//!
+//! Supports `*` globs, a trailing `/` for directory-only patterns, and `!`
+//! negations, matched one path segment at a time. Patterns accumulate as the
+//! walk descends, so a rule in a root `.gitignore` still excludes matching
+//! names several directories deep, not just its own direct children.
+
+use std::fs;
+use std::path::Path;
+
+pub(crate) struct Gitignore {
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Clone)]
+struct Pattern {
+    glob: String,
+    dir_only: bool,
+    negated: bool,
+}
+
+impl Gitignore {
+    /// The empty rule set, for starting a walk at the repository root.
+    pub(crate) fn empty() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Layers `dir`'s own `.gitignore` (if any) after the inherited patterns in
+    /// `self`, so a subdirectory's rules can see and override its ancestors'.
+    pub(crate) fn extend(&self, dir: &Path) -> Self {
+        let mut patterns = self.patterns.clone();
+        if let Ok(content) = fs::read_to_string(dir.join(".gitignore")) {
+            patterns.extend(content.lines().filter_map(parse_line));
+        }
+        Self { patterns }
+    }
+
+    /// Whether `name` (a direct child of the directory this was loaded for)
+    /// should be excluded from the tree. Later patterns win, so a `!` rule can
+    /// re-include something an earlier pattern excluded.
+    pub(crate) fn is_ignored(&self, name: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for pattern in &self.patterns {
+            if pattern.dir_only && !is_dir {
+                continue;
+            }
+            if glob_match(&pattern.glob, name) {
+                ignored = !pattern.negated;
+            }
+        }
+        ignored
+    }
+}
+
+fn parse_line(line: &str) -> Option<Pattern> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let negated = line.starts_with('!');
+    let line = line.strip_prefix('!').unwrap_or(line);
+    let dir_only = line.ends_with('/');
+    let glob = line.trim_end_matches('/').to_string();
+    Some(Pattern {
+        glob,
+        dir_only,
+        negated,
+    })
+}
+
+/// Matches a single `.gitignore` glob segment against `name`, where `*`
+/// matches any run of characters and every other byte must match literally.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    fn go(pattern: &[u8], name: &[u8]) -> bool {
+        match pattern.first() {
+            None => name.is_empty(),
+            Some(b'*') => (0..=name.len()).any(|i| go(&pattern[1..], &name[i..])),
+            Some(c) => name.first() == Some(c) && go(&pattern[1..], &name[1..]),
+        }
+    }
+    go(pattern.as_bytes(), name.as_bytes())
+}
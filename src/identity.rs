@@ -0,0 +1,112 @@
+//! Resolves the author/committer identity and timestamp used when building commits.
+//!
+//! Priority order, matching real Git: `GIT_<ROLE>_NAME`/`GIT_<ROLE>_EMAIL` and
+//! `GIT_<ROLE>_DATE` environment variables, then `.git/config`, then
+//! `~/.gitconfig`, then a hardcoded fallback.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Offset};
+
+const DEFAULT_NAME: &str = "fsiraj";
+const DEFAULT_EMAIL: &str = "fsiraj@git.com";
+
+pub(crate) struct Identity {
+    pub(crate) name: String,
+    pub(crate) email: String,
+}
+
+/// Resolves the identity for `role` (`"AUTHOR"` or `"COMMITTER"`).
+pub(crate) fn resolve_identity(role: &str) -> Identity {
+    let (config_name, config_email) = read_user_section(Path::new(".git/config"));
+    let (global_name, global_email) = home_gitconfig_path()
+        .map(|path| read_user_section(&path))
+        .unwrap_or((None, None));
+
+    let name = env::var(format!("GIT_{role}_NAME"))
+        .ok()
+        .or(config_name)
+        .or(global_name)
+        .unwrap_or_else(|| DEFAULT_NAME.to_string());
+    let email = env::var(format!("GIT_{role}_EMAIL"))
+        .ok()
+        .or(config_email)
+        .or(global_email)
+        .unwrap_or_else(|| DEFAULT_EMAIL.to_string());
+
+    Identity { name, email }
+}
+
+/// Resolves the `<epoch> <tz>` timestamp for `role` (`"AUTHOR"` or `"COMMITTER"`),
+/// honoring a `GIT_<ROLE>_DATE` override if present.
+pub(crate) fn resolve_timestamp(role: &str) -> String {
+    match env::var(format!("GIT_{role}_DATE")) {
+        Ok(raw) => parse_date_override(&raw),
+        Err(_) => current_timestamp_str(),
+    }
+}
+
+/// Reads the `[user] name = ... / email = ...` fields out of a gitconfig-style file.
+fn read_user_section(path: &Path) -> (Option<String>, Option<String>) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return (None, None);
+    };
+
+    let mut in_user_section = false;
+    let mut name = None;
+    let mut email = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            in_user_section = section.eq_ignore_ascii_case("user");
+            continue;
+        }
+        if !in_user_section {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            match key.trim() {
+                "name" => name = Some(value.trim().to_string()),
+                "email" => email = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+    (name, email)
+}
+
+fn home_gitconfig_path() -> Option<PathBuf> {
+    env::var("HOME").ok().map(|home| Path::new(&home).join(".gitconfig"))
+}
+
+/// Accepts either the raw `<epoch> <tz>` form `current_timestamp_str` produces, or an
+/// RFC 2822/3339 date string, and normalizes both to `<epoch> <tz>`.
+fn parse_date_override(raw: &str) -> String {
+    let raw = raw.trim();
+
+    if let Some((epoch, tz)) = raw.split_once(' ') {
+        if let Ok(epoch) = epoch.parse::<i64>() {
+            return format!("{epoch} {tz}");
+        }
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc2822(raw) {
+        return format!("{} {}", parsed.timestamp(), parsed.format("%z"));
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
+        return format!("{} {}", parsed.timestamp(), parsed.format("%z"));
+    }
+
+    panic!("unrecognized date format: {raw:?}");
+}
+
+fn current_timestamp_str() -> String {
+    let now = Local::now();
+    let timestamp = now.timestamp();
+    let offset = now.offset().fix().local_minus_utc();
+    let sign = if offset < 0 { '-' } else { '+' };
+    let hours = offset.abs() / 3600;
+    let minutes = (offset.abs() % 3600) / 60;
+    format!("{timestamp} {sign}{hours:02}{minutes:02}")
+}
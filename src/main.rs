@@ -6,19 +6,27 @@ use std::fs;
 use std::io;
 use std::io::Read;
 use std::ops;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use clap::{Parser, Subcommand};
-use chrono::Local;
-use chrono::Offset;
+use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::Utc;
 use flate2::Compression;
 use flate2::bufread::ZlibDecoder;
 use flate2::bufread::ZlibEncoder;
 use sha1::Digest;
 use sha1::Sha1;
 
-#[derive(Debug, Clone)]
-struct Sha1Hash(String);
+mod gitignore;
+mod identity;
+mod packfile;
+mod transport;
+
+use gitignore::Gitignore;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Sha1Hash(String);
 
 impl ops::Deref for Sha1Hash {
     type Target = String;
@@ -43,13 +51,26 @@ impl Sha1Hash {
         }
         bytes
     }
+
+    /// Builds a `Sha1Hash` from its raw 20-byte form, the inverse of `as_bytes`.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Self {
+        assert_eq!(bytes.len(), 20, "sha-1 must be exactly 20 bytes");
+        Self(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Builds a `Sha1Hash` from its 40-character hex form.
+    pub(crate) fn from_hex(hex: &str) -> Self {
+        assert_eq!(hex.len(), 40, "sha-1 hex string must be 40 characters");
+        Self(hex.to_string())
+    }
 }
 
 #[derive(Debug, Clone)]
-enum GitObjectKind {
+pub(crate) enum GitObjectKind {
     Blob,
     Tree,
     Commit,
+    Tag,
 }
 
 impl GitObjectKind {
@@ -58,6 +79,7 @@ impl GitObjectKind {
             Self::Blob => "blob",
             Self::Tree => "tree",
             Self::Commit => "commit",
+            Self::Tag => "tag",
         }
     }
 }
@@ -100,13 +122,31 @@ impl TreeEntry {
 }
 
 #[derive(Debug)]
-struct GitObject {
+pub(crate) struct GitObject {
     kind: GitObjectKind,
     size: usize,
     content: Vec<u8>,
 }
 
 impl GitObject {
+    /// Constructs a GitObject directly from a kind and raw content, deriving `size`
+    pub(crate) fn new(kind: GitObjectKind, content: Vec<u8>) -> Self {
+        let size = content.len();
+        Self {
+            kind,
+            size,
+            content,
+        }
+    }
+
+    pub(crate) fn kind(&self) -> &GitObjectKind {
+        &self.kind
+    }
+
+    pub(crate) fn content(&self) -> &[u8] {
+        &self.content
+    }
+
     /// Constructs the file content of the GitObject
     fn serialize(&self) -> Vec<u8> {
         let mut result = Vec::<u8>::new();
@@ -194,7 +234,14 @@ impl GitObject {
                 .collect::<String>();
             let hash = Sha1Hash(hash);
 
-            let kind = GitObject::from(hash.clone()).kind;
+            // Derived from `mode` rather than opened: a 160000 gitlink hash
+            // points into a nested repo's object store, not this one's, so
+            // resolving it here would fail (or resolve the wrong object).
+            let kind = match mode {
+                160000 => GitObjectKind::Commit,
+                40000 => GitObjectKind::Tree,
+                _ => GitObjectKind::Blob,
+            };
 
             result.push(TreeEntry {
                 mode,
@@ -207,6 +254,86 @@ impl GitObject {
 
         result
     }
+
+    /// Parses the content as a Git Commit
+    fn parse_as_commit(&self) -> CommitInfo {
+        assert!(matches!(self.kind, GitObjectKind::Commit));
+        let text = String::from_utf8_lossy(&self.content);
+        let mut lines = text.lines();
+
+        let mut tree = None;
+        let mut parents = Vec::new();
+        let mut author = None;
+        let (mut author_timestamp, mut author_tz) = (0i64, String::new());
+        let mut committer = None;
+        let (mut committer_timestamp, mut committer_tz) = (0i64, String::new());
+
+        for line in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            if let Some(rest) = line.strip_prefix("tree ") {
+                tree = Some(Sha1Hash::from_hex(rest));
+            } else if let Some(rest) = line.strip_prefix("parent ") {
+                parents.push(Sha1Hash::from_hex(rest));
+            } else if let Some(rest) = line.strip_prefix("author ") {
+                let (name, timestamp, tz) = parse_identity_line(rest);
+                author = Some(name);
+                author_timestamp = timestamp;
+                author_tz = tz;
+            } else if let Some(rest) = line.strip_prefix("committer ") {
+                let (name, timestamp, tz) = parse_identity_line(rest);
+                committer = Some(name);
+                committer_timestamp = timestamp;
+                committer_tz = tz;
+            }
+        }
+        let message = lines.collect::<Vec<_>>().join("\n");
+
+        CommitInfo {
+            tree: tree.expect("commit missing tree header"),
+            parents,
+            author: author.expect("commit missing author header"),
+            author_timestamp,
+            author_tz,
+            committer: committer.expect("commit missing committer header"),
+            committer_timestamp,
+            committer_tz,
+            message,
+        }
+    }
+}
+
+/// A single `author`/`committer` line's `Name <email>`, epoch seconds, and `+HHMM`/`-HHMM` zone.
+fn parse_identity_line(rest: &str) -> (String, i64, String) {
+    let mut parts = rest.rsplitn(3, ' ');
+    let tz = parts.next().expect("identity line missing timezone").to_string();
+    let timestamp = parts
+        .next()
+        .expect("identity line missing timestamp")
+        .parse::<i64>()
+        .expect("invalid timestamp");
+    let name = parts
+        .next()
+        .expect("identity line missing name/email")
+        .to_string();
+    (name, timestamp, tz)
+}
+
+/// The parsed fields of a Commit GitObject, as produced by `parse_as_commit`.
+struct CommitInfo {
+    tree: Sha1Hash,
+    parents: Vec<Sha1Hash>,
+    author: String,
+    author_timestamp: i64,
+    author_tz: String,
+    #[allow(dead_code)]
+    committer: String,
+    #[allow(dead_code)]
+    committer_timestamp: i64,
+    #[allow(dead_code)]
+    committer_tz: String,
+    message: String,
 }
 
 impl From<Sha1Hash> for GitObject {
@@ -239,6 +366,8 @@ impl From<Sha1Hash> for GitObject {
         let kind = match kind_str {
             "blob" => GitObjectKind::Blob,
             "tree" => GitObjectKind::Tree,
+            "commit" => GitObjectKind::Commit,
+            "tag" => GitObjectKind::Tag,
             _ => panic!("invalid git object kind"),
         };
         let size_str =
@@ -255,65 +384,145 @@ impl From<Sha1Hash> for GitObject {
 }
 
 impl From<&Path> for GitObject {
-    /// Constructs a Git Blob or Tree from a Path to any file or directory
+    /// Constructs a Git Blob or Tree from a Path to any file, symlink, or directory
     fn from(path: &Path) -> Self {
-        if path.is_file() {
-            // Blob
-            let kind = GitObjectKind::Blob;
-            let content = fs::read(path).expect("file could not be opened or read");
-            let size = content.len();
-            Self {
-                kind,
-                size,
-                content,
-            }
+        build_object(path, &Gitignore::empty())
+    }
+}
+
+/// The recursive worker behind `From<&Path>`. Threads `inherited_ignore` down
+/// through the walk so a `.gitignore` rule several directories up still
+/// excludes matching names deeper in the tree, not just its own children.
+fn build_object(path: &Path, inherited_ignore: &Gitignore) -> GitObject {
+    let metadata = fs::symlink_metadata(path).expect("could not stat path");
+    if metadata.is_symlink() {
+        // Symlink: blob content is the link target
+        let target = fs::read_link(path).expect("could not read symlink target");
+        return GitObject::new(
+            GitObjectKind::Blob,
+            target.to_string_lossy().into_owned().into_bytes(),
+        );
+    }
+    if metadata.is_file() {
+        // Blob
+        let content = fs::read(path).expect("file could not be opened or read");
+        return GitObject::new(GitObjectKind::Blob, content);
+    }
+
+    // Tree
+    let ignore = inherited_ignore.extend(path);
+    let mut tree_entries = Vec::<TreeEntry>::new();
+    for entry in fs::read_dir(path).expect("unable to read directory") {
+        let entry = entry.expect("unable to read entry in directory");
+        let entry_path = entry.path();
+        let name = entry_path
+            .file_name()
+            .expect("expected a filename")
+            .to_string_lossy()
+            .to_string();
+        if name == ".git" {
+            continue;
+        }
+        let entry_metadata =
+            fs::symlink_metadata(&entry_path).expect("could not stat directory entry");
+        if ignore.is_ignored(&name, entry_metadata.is_dir()) {
+            continue;
+        }
+
+        if entry_metadata.is_dir() && is_nested_repo(&entry_path) {
+            tree_entries.push(TreeEntry {
+                mode: 160000,
+                kind: GitObjectKind::Commit,
+                hash: read_gitlink_hash(&entry_path),
+                name,
+            });
+            continue;
+        }
+        if entry_metadata.is_dir() && fs::read_dir(&entry_path).unwrap().next().is_none() {
+            continue;
+        }
+
+        let mode = if entry_metadata.is_symlink() {
+            120000
+        } else if entry_metadata.is_dir() {
+            40000
+        } else if is_executable(&entry_metadata) {
+            100755
         } else {
-            // Tree
-            let kind = GitObjectKind::Tree;
-            // Construct tree entries
-            let mut tree_entries = Vec::<TreeEntry>::new();
-            for entry in fs::read_dir(path).expect("unable to read directory") {
-                let entry = entry.expect("unable to read entry in directory");
-                let entry_path = entry.path();
-                let name = entry_path
-                    .file_name()
-                    .expect("expected a filename")
-                    .to_string_lossy()
-                    .to_string();
-                if name == ".git" {
-                    continue;
-                }
-                if entry_path.is_dir() && fs::read_dir(&entry_path).unwrap().next().is_none() {
-                    continue;
-                }
-                let mode = if entry_path.is_dir() { 40000 } else { 100644 };
-                let git_object = GitObject::from(entry_path.as_path());
-                git_object.write();
-                let hash = git_object.hash();
-                let tree_entry = TreeEntry {
-                    mode,
-                    kind: git_object.kind.clone(),
-                    hash,
-                    name,
-                };
-                tree_entries.push(tree_entry);
-            }
-            // Sort them and then generate content bytes
-            tree_entries.sort_by_key(|entry| entry.name.clone());
-            let mut content = Vec::<u8>::new();
-            for entry in tree_entries {
-                content.extend_from_slice(&entry.serialize());
-            }
-            let size = content.len();
-            Self {
-                kind,
-                size,
-                content,
-            }
+            100644
+        };
+        let git_object = build_object(entry_path.as_path(), &ignore);
+        git_object.write();
+        let hash = git_object.hash();
+        tree_entries.push(TreeEntry {
+            mode,
+            kind: git_object.kind().clone(),
+            hash,
+            name,
+        });
+    }
+    // Sort them and then generate content bytes
+    tree_entries.sort_by_key(|entry| entry.name.clone());
+    let mut content = Vec::<u8>::new();
+    for entry in tree_entries {
+        content.extend_from_slice(&entry.serialize());
+    }
+    GitObject::new(GitObjectKind::Tree, content)
+}
+
+/// A directory containing its own `.git` is a nested repository, staged as a gitlink.
+fn is_nested_repo(path: &Path) -> bool {
+    path.join(".git").exists()
+}
+
+/// Resolves a nested repository's checked-out commit by following its `HEAD`.
+fn read_gitlink_hash(repo_path: &Path) -> Sha1Hash {
+    let git_dir = resolve_git_dir(repo_path);
+    let head = fs::read_to_string(git_dir.join("HEAD")).expect("could not read nested repo HEAD");
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(ref_path) => {
+            let hash = fs::read_to_string(git_dir.join(ref_path))
+                .expect("could not resolve nested repo ref");
+            Sha1Hash::from_hex(hash.trim())
         }
+        None => Sha1Hash::from_hex(head),
+    }
+}
+
+/// Resolves `repo_path`'s `.git` to the directory holding its metadata. For a
+/// regular repo `.git` is that directory directly; for a submodule checkout
+/// it's a file containing `gitdir: <path>` pointing at the real one (under the
+/// superproject's `.git/modules/`), possibly relative to `repo_path`.
+fn resolve_git_dir(repo_path: &Path) -> PathBuf {
+    let dot_git = repo_path.join(".git");
+    if dot_git.is_dir() {
+        return dot_git;
+    }
+    let contents = fs::read_to_string(&dot_git).expect("could not read .git file");
+    let gitdir = contents
+        .trim()
+        .strip_prefix("gitdir: ")
+        .expect("unrecognized .git file format");
+    let gitdir = PathBuf::from(gitdir);
+    if gitdir.is_absolute() {
+        gitdir
+    } else {
+        repo_path.join(gitdir)
     }
 }
 
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
 impl From<(Sha1Hash, Option<Sha1Hash>, String)> for GitObject {
     fn from(hashes: (Sha1Hash, Option<Sha1Hash>, String)) -> Self {
         let (tree_hash, parent_hash, message) = hashes;
@@ -323,10 +532,15 @@ impl From<(Sha1Hash, Option<Sha1Hash>, String)> for GitObject {
         if let Some(parent_hash) = parent_hash {
             content.extend_from_slice(format!("parent {}\n", parent_hash).as_bytes());
         }
-        let timestamp = get_timestamp_str();
-        for field in ["author", "committer"] {
+        for (field, role) in [("author", "AUTHOR"), ("committer", "COMMITTER")] {
+            let identity = identity::resolve_identity(role);
+            let timestamp = identity::resolve_timestamp(role);
             content.extend_from_slice(
-                format!("{} fsiraj <fsiraj@git.com> {}\n", field, timestamp).as_bytes(),
+                format!(
+                    "{} {} <{}> {}\n",
+                    field, identity.name, identity.email, timestamp
+                )
+                .as_bytes(),
             );
         }
         content.push(b'\n');
@@ -341,14 +555,360 @@ impl From<(Sha1Hash, Option<Sha1Hash>, String)> for GitObject {
     }
 }
 
-fn get_timestamp_str() -> String {
-    let now = Local::now();
-    let timestamp = now.timestamp();
-    let offset = now.offset().fix().local_minus_utc();
-    let hours = offset / 3600;
-    let minutes = (offset.abs() % 3600) / 60;
-    let timezone = format!("{:+03}{:02}", hours, minutes);
-    format!("{} {}", timestamp, timezone)
+#[derive(Debug, Clone)]
+enum DiffLine {
+    Context(String),
+    Insertion(String),
+    Deletion(String),
+}
+
+/// A single `@@ -l,s +l,s @@` hunk of a unified diff between two blobs.
+#[derive(Debug)]
+struct DiffHunk {
+    old_start: usize,
+    old_lines: usize,
+    new_start: usize,
+    new_lines: usize,
+    lines: Vec<DiffLine>,
+}
+
+impl fmt::Display for DiffHunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_lines, self.new_start, self.new_lines
+        )?;
+        for line in &self.lines {
+            match line {
+                DiffLine::Context(text) => writeln!(f, " {text}")?,
+                DiffLine::Insertion(text) => writeln!(f, "+{text}")?,
+                DiffLine::Deletion(text) => writeln!(f, "-{text}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum DiffEntry {
+    Added { path: String, hash: Sha1Hash },
+    Deleted { path: String, hash: Sha1Hash },
+    Renamed { from: String, to: String, hash: Sha1Hash },
+    Modified { path: String, hunks: Vec<DiffHunk> },
+    GitlinkChanged { path: String, old_hash: Sha1Hash, new_hash: Sha1Hash },
+}
+
+impl fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added { path, hash } => writeln!(f, "added    {path} ({hash})"),
+            Self::Deleted { path, hash } => writeln!(f, "deleted  {path} ({hash})"),
+            Self::Renamed { from, to, hash } => writeln!(f, "renamed  {from} -> {to} ({hash})"),
+            Self::Modified { path, hunks } => {
+                writeln!(f, "modified {path}")?;
+                for hunk in hunks {
+                    write!(f, "{hunk}")?;
+                }
+                Ok(())
+            }
+            Self::GitlinkChanged { path, old_hash, new_hash } => {
+                writeln!(f, "gitlink  {path} ({old_hash} -> {new_hash})")
+            }
+        }
+    }
+}
+
+impl GitObject {
+    /// Resolves `hash` to its tree entries, dereferencing a commit's `tree` line first.
+    fn resolve_tree(hash: Sha1Hash) -> Vec<TreeEntry> {
+        let object = GitObject::from(hash);
+        match object.kind {
+            GitObjectKind::Tree => object.parse_as_tree(),
+            GitObjectKind::Commit => GitObject::from(object.parse_as_commit().tree).parse_as_tree(),
+            other => panic!("expected a tree or commit object, found a {other}"),
+        }
+    }
+
+    /// Diffs two sorted tree entry lists (as `write-tree` already produces), recursing
+    /// into subtrees whose hashes differ and diffing the content of changed blobs.
+    fn diff_trees(a: &[TreeEntry], b: &[TreeEntry]) -> Vec<DiffEntry> {
+        let mut added = Vec::new();
+        let mut deleted = Vec::new();
+        let mut entries = Vec::new();
+
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            match (a.get(i), b.get(j)) {
+                (Some(old), Some(new)) if old.name == new.name => {
+                    if old.hash != new.hash {
+                        match (&old.kind, &new.kind) {
+                            (GitObjectKind::Tree, GitObjectKind::Tree) => {
+                                let old_entries = GitObject::from(old.hash.clone()).parse_as_tree();
+                                let new_entries = GitObject::from(new.hash.clone()).parse_as_tree();
+                                entries.extend(prefix_paths(
+                                    &old.name,
+                                    Self::diff_trees(&old_entries, &new_entries),
+                                ));
+                            }
+                            (GitObjectKind::Commit, GitObjectKind::Commit) => {
+                                // Gitlink: `old.hash`/`new.hash` are commits inside
+                                // the *submodule's* object store, unresolvable here,
+                                // so just report the pointer change.
+                                entries.push(DiffEntry::GitlinkChanged {
+                                    path: old.name.clone(),
+                                    old_hash: old.hash.clone(),
+                                    new_hash: new.hash.clone(),
+                                });
+                            }
+                            (GitObjectKind::Tree, _)
+                            | (_, GitObjectKind::Tree)
+                            | (GitObjectKind::Commit, _)
+                            | (_, GitObjectKind::Commit) => {
+                                // A path changed kind (e.g. a file replaced by a
+                                // directory of the same name) — there's no sensible
+                                // blob diff between the two, so treat it as a
+                                // straight delete-then-add instead.
+                                entries.push(DiffEntry::Deleted {
+                                    path: old.name.clone(),
+                                    hash: old.hash.clone(),
+                                });
+                                entries.push(DiffEntry::Added {
+                                    path: new.name.clone(),
+                                    hash: new.hash.clone(),
+                                });
+                            }
+                            _ => {
+                                let old_blob = GitObject::from(old.hash.clone()).parse_as_blob();
+                                let new_blob = GitObject::from(new.hash.clone()).parse_as_blob();
+                                entries.push(DiffEntry::Modified {
+                                    path: old.name.clone(),
+                                    hunks: diff_blobs(&old_blob, &new_blob),
+                                });
+                            }
+                        }
+                    }
+                    i += 1;
+                    j += 1;
+                }
+                (Some(old), Some(new)) if old.name < new.name => {
+                    deleted.push(old);
+                    i += 1;
+                }
+                (Some(_), Some(new)) => {
+                    added.push(new);
+                    j += 1;
+                }
+                (Some(old), None) => {
+                    deleted.push(old);
+                    i += 1;
+                }
+                (None, Some(new)) => {
+                    added.push(new);
+                    j += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+
+        // Same-hash add/delete pairs are really renames, not independent changes.
+        let mut renamed = vec![false; added.len()];
+        for old in &deleted {
+            let rename_idx = added
+                .iter()
+                .enumerate()
+                .find(|(idx, new)| !renamed[*idx] && new.hash == old.hash)
+                .map(|(idx, _)| idx);
+            match rename_idx {
+                Some(idx) => {
+                    renamed[idx] = true;
+                    entries.push(DiffEntry::Renamed {
+                        from: old.name.clone(),
+                        to: added[idx].name.clone(),
+                        hash: old.hash.clone(),
+                    });
+                }
+                None => entries.push(DiffEntry::Deleted {
+                    path: old.name.clone(),
+                    hash: old.hash.clone(),
+                }),
+            }
+        }
+        for (idx, new) in added.iter().enumerate() {
+            if !renamed[idx] {
+                entries.push(DiffEntry::Added {
+                    path: new.name.clone(),
+                    hash: new.hash.clone(),
+                });
+            }
+        }
+
+        entries
+    }
+}
+
+/// Prefixes every path touched by a recursive subtree diff with its parent directory.
+fn prefix_paths(dir: &str, entries: Vec<DiffEntry>) -> Vec<DiffEntry> {
+    entries
+        .into_iter()
+        .map(|entry| match entry {
+            DiffEntry::Added { path, hash } => DiffEntry::Added {
+                path: format!("{dir}/{path}"),
+                hash,
+            },
+            DiffEntry::Deleted { path, hash } => DiffEntry::Deleted {
+                path: format!("{dir}/{path}"),
+                hash,
+            },
+            DiffEntry::Renamed { from, to, hash } => DiffEntry::Renamed {
+                from: format!("{dir}/{from}"),
+                to: format!("{dir}/{to}"),
+                hash,
+            },
+            DiffEntry::Modified { path, hunks } => DiffEntry::Modified {
+                path: format!("{dir}/{path}"),
+                hunks,
+            },
+            DiffEntry::GitlinkChanged { path, old_hash, new_hash } => DiffEntry::GitlinkChanged {
+                path: format!("{dir}/{path}"),
+                old_hash,
+                new_hash,
+            },
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DiffOp {
+    Context,
+    Insertion,
+    Deletion,
+}
+
+/// Diffs two blobs' contents: builds the LCS of their line vectors, walks it to
+/// classify every line as context/insertion/deletion, then groups adjacent
+/// changes into hunks with a few lines of surrounding context.
+fn diff_blobs(old: &str, new: &str) -> Vec<DiffHunk> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = lcs_diff(&old_lines, &new_lines);
+    group_into_hunks(&ops, 3)
+}
+
+/// Computes the LCS of `old` and `new` via a standard O(n*m) DP table, then
+/// backtracks it into a flat edit script of context/insertion/deletion ops.
+fn lcs_diff(old: &[&str], new: &[&str]) -> Vec<(DiffOp, String)> {
+    let (n, m) = (old.len(), new.len());
+    let mut dp = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[i][j] = if old[i] == new[j] {
+                dp[i + 1][j + 1] + 1
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push((DiffOp::Context, old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if dp[i + 1][j] >= dp[i][j + 1] {
+            ops.push((DiffOp::Deletion, old[i].to_string()));
+            i += 1;
+        } else {
+            ops.push((DiffOp::Insertion, new[j].to_string()));
+            j += 1;
+        }
+    }
+    while i < n {
+        ops.push((DiffOp::Deletion, old[i].to_string()));
+        i += 1;
+    }
+    while j < m {
+        ops.push((DiffOp::Insertion, new[j].to_string()));
+        j += 1;
+    }
+    ops
+}
+
+/// Groups a flat edit script into unified-diff hunks, clustering changes that
+/// are within `2 * context` lines of each other and keeping `context` unchanged
+/// lines around each cluster.
+fn group_into_hunks(ops: &[(DiffOp, String)], context: usize) -> Vec<DiffHunk> {
+    let change_indices: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| !matches!(op, DiffOp::Context))
+        .map(|(i, _)| i)
+        .collect();
+    if change_indices.is_empty() {
+        return Vec::new();
+    }
+
+    let mut clusters = Vec::new();
+    let (mut start, mut end) = (change_indices[0], change_indices[0]);
+    for &idx in &change_indices[1..] {
+        if idx - end <= context * 2 {
+            end = idx;
+        } else {
+            clusters.push((start, end));
+            start = idx;
+            end = idx;
+        }
+    }
+    clusters.push((start, end));
+
+    // old_at[i]/new_at[i]: 1-based line number at the start of ops[i].
+    let mut old_at = vec![1usize; ops.len() + 1];
+    let mut new_at = vec![1usize; ops.len() + 1];
+    for (i, (op, _)) in ops.iter().enumerate() {
+        old_at[i + 1] = old_at[i] + usize::from(!matches!(op, DiffOp::Insertion));
+        new_at[i + 1] = new_at[i] + usize::from(!matches!(op, DiffOp::Deletion));
+    }
+
+    clusters
+        .into_iter()
+        .map(|(start, end)| {
+            let lo = start.saturating_sub(context);
+            let hi = (end + context + 1).min(ops.len());
+            let lines = ops[lo..hi]
+                .iter()
+                .map(|(op, text)| match op {
+                    DiffOp::Context => DiffLine::Context(text.clone()),
+                    DiffOp::Insertion => DiffLine::Insertion(text.clone()),
+                    DiffOp::Deletion => DiffLine::Deletion(text.clone()),
+                })
+                .collect();
+            DiffHunk {
+                old_start: old_at[lo],
+                old_lines: old_at[hi] - old_at[lo],
+                new_start: new_at[lo],
+                new_lines: new_at[hi] - new_at[lo],
+                lines,
+            }
+        })
+        .collect()
+}
+
+/// Formats a signed epoch timestamp (pre-1970 commits included) in the given
+/// `+HHMM`/`-HHMM` zone, the inverse of `identity::current_timestamp_str`'s encoding.
+fn format_local_time(timestamp: i64, tz: &str) -> String {
+    let sign = if tz.starts_with('-') { -1 } else { 1 };
+    let digits = &tz[1..];
+    let hours: i32 = digits[0..2].parse().expect("invalid timezone hours");
+    let minutes: i32 = digits[2..4].parse().expect("invalid timezone minutes");
+    let offset = FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        .expect("invalid timezone offset");
+
+    let utc = DateTime::<Utc>::from_timestamp(timestamp, 0).expect("invalid commit timestamp");
+    utc.with_timezone(&offset)
+        .format("%a %b %e %H:%M:%S %Y %z")
+        .to_string()
 }
 
 #[derive(Parser)]
@@ -400,6 +960,23 @@ enum Commands {
         #[arg(short = 'm')]
         message: String,
     },
+    /// Clone a repository over the Git smart HTTP protocol
+    Clone {
+        /// The repository URL, e.g. https://github.com/org/repo.git
+        url: String,
+    },
+    /// Show changes between two trees or commits
+    Diff {
+        /// Tree or commit hash to diff from
+        from: String,
+        /// Tree or commit hash to diff to
+        to: String,
+    },
+    /// Show commit logs, following first parents back from a starting commit
+    Log {
+        /// Commit hash to start from
+        hash: String,
+    },
 }
 
 
@@ -458,5 +1035,38 @@ fn main() {
             commit.write();
             println!("{}", commit.hash());
         }
+        Commands::Clone { url } => {
+            fs::create_dir(".git").unwrap();
+            fs::create_dir(".git/objects").unwrap();
+            fs::create_dir_all(".git/refs/heads").unwrap();
+            fs::write(".git/HEAD", "ref: refs/heads/main\n").unwrap();
+
+            let head = transport::clone(&url);
+            fs::write(".git/refs/heads/main", format!("{}\n", head)).unwrap();
+            println!("Cloned into current directory, HEAD is at {head}");
+        }
+        Commands::Diff { from, to } => {
+            let from_entries = GitObject::resolve_tree(Sha1Hash(from));
+            let to_entries = GitObject::resolve_tree(Sha1Hash(to));
+            for entry in GitObject::diff_trees(&from_entries, &to_entries) {
+                print!("{entry}");
+            }
+        }
+        Commands::Log { hash } => {
+            let mut current = Some(Sha1Hash(hash));
+            while let Some(hash) = current {
+                let commit = GitObject::from(hash.clone()).parse_as_commit();
+                println!("commit {hash}");
+                println!("Author: {}", commit.author);
+                println!(
+                    "Date:   {}",
+                    format_local_time(commit.author_timestamp, &commit.author_tz)
+                );
+                println!();
+                println!("    {}", commit.message);
+                println!();
+                current = commit.parents.into_iter().next();
+            }
+        }
     }
 }
@@ -0,0 +1,322 @@
+//! Reading and writing Git packfiles, including ofs-delta/ref-delta resolution.
+//!
+//! A packfile is a `PACK` signature, a big-endian version and object count,
+//! followed by that many entries (variable-length type+size header, then the
+//! zlib-compressed object or delta data), and a trailing 20-byte SHA-1 over
+//! everything before it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::bufread::{ZlibDecoder, ZlibEncoder};
+use flate2::Compression;
+use sha1::{Digest, Sha1};
+
+use crate::{GitObject, GitObjectKind, Sha1Hash};
+
+const PACK_SIGNATURE: &[u8; 4] = b"PACK";
+const PACK_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    Commit,
+    Tree,
+    Blob,
+    Tag,
+    OfsDelta,
+    RefDelta,
+}
+
+impl EntryKind {
+    fn from_type_bits(bits: u8) -> Self {
+        match bits {
+            1 => Self::Commit,
+            2 => Self::Tree,
+            3 => Self::Blob,
+            4 => Self::Tag,
+            6 => Self::OfsDelta,
+            7 => Self::RefDelta,
+            other => panic!("invalid packfile object type {other}"),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn to_type_bits(kind: &GitObjectKind) -> u8 {
+        match kind {
+            GitObjectKind::Commit => 1,
+            GitObjectKind::Tree => 2,
+            GitObjectKind::Blob => 3,
+            GitObjectKind::Tag => 4,
+        }
+    }
+}
+
+/// One entry as read off the wire, before delta resolution.
+struct RawEntry {
+    kind: EntryKind,
+    /// Byte offset of this entry's header within the packfile.
+    offset: usize,
+    base_offset: Option<usize>,
+    base_hash: Option<Sha1Hash>,
+    /// Inflated object content, or inflated delta instructions for the two delta kinds.
+    data: Vec<u8>,
+}
+
+/// Parses the packfile at `path`, resolves every delta against its base, writes
+/// each resolved object as a loose object, and returns them in pack order.
+pub(crate) fn unpack(path: &Path) -> Vec<GitObject> {
+    let bytes = fs::read(path).expect("could not read packfile");
+    verify_trailer(&bytes);
+
+    assert_eq!(&bytes[0..4], PACK_SIGNATURE, "missing PACK signature");
+    let version = u32::from_be_bytes(bytes[4..8].try_into().unwrap());
+    assert_eq!(version, PACK_VERSION, "unsupported packfile version");
+    let count = u32::from_be_bytes(bytes[8..12].try_into().unwrap()) as usize;
+
+    let mut cursor = 12;
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        let offset = cursor;
+        let (kind, size, header_len) = read_entry_header(&bytes, cursor);
+        cursor += header_len;
+
+        let base_offset = (kind == EntryKind::OfsDelta).then(|| {
+            let (delta, len) = read_ofs_delta_offset(&bytes, cursor);
+            cursor += len;
+            offset - delta
+        });
+        let base_hash = (kind == EntryKind::RefDelta).then(|| {
+            let hash = Sha1Hash::from_bytes(&bytes[cursor..cursor + 20]);
+            cursor += 20;
+            hash
+        });
+
+        let (data, consumed) = inflate_at(&bytes, cursor, size);
+        cursor += consumed;
+
+        entries.push(RawEntry {
+            kind,
+            offset,
+            base_offset,
+            base_hash,
+            data,
+        });
+    }
+
+    resolve(entries)
+}
+
+/// Serializes `objects` into a non-deltified packfile (every entry stored whole).
+// Not called yet: there's no `push` subcommand in this crate to exercise it.
+#[allow(dead_code)]
+pub(crate) fn pack(objects: &[GitObject]) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(PACK_SIGNATURE);
+    body.extend_from_slice(&PACK_VERSION.to_be_bytes());
+    body.extend_from_slice(&(objects.len() as u32).to_be_bytes());
+
+    for object in objects {
+        write_entry_header(
+            &mut body,
+            EntryKind::to_type_bits(object.kind()),
+            object.content().len(),
+        );
+        let mut encoder = ZlibEncoder::new(object.content(), Compression::fast());
+        encoder
+            .read_to_end(&mut body)
+            .expect("compression failed");
+    }
+
+    let mut hasher = Sha1::new();
+    hasher.update(&body);
+    body.extend_from_slice(&hasher.finalize());
+    body
+}
+
+/// Resolves every delta in pack order, writing each result as a loose object.
+///
+/// Bases always precede their deltas by byte offset in a non-thin pack, so a
+/// single forward pass is enough; ref-deltas additionally fall back to the
+/// loose object store in case the base was already present there.
+fn resolve(entries: Vec<RawEntry>) -> Vec<GitObject> {
+    let mut by_offset: HashMap<usize, (GitObjectKind, Vec<u8>)> = HashMap::new();
+    let mut by_hash: HashMap<String, (GitObjectKind, Vec<u8>)> = HashMap::new();
+    let mut result = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let (kind, content) = match entry.kind {
+            EntryKind::Commit => (GitObjectKind::Commit, entry.data),
+            EntryKind::Tree => (GitObjectKind::Tree, entry.data),
+            EntryKind::Blob => (GitObjectKind::Blob, entry.data),
+            EntryKind::Tag => (GitObjectKind::Tag, entry.data),
+            EntryKind::OfsDelta => {
+                let base_offset = entry.base_offset.expect("ofs-delta missing base offset");
+                let (base_kind, base_content) = by_offset
+                    .get(&base_offset)
+                    .expect("ofs-delta base was not resolved before its delta");
+                (base_kind.clone(), apply_delta(base_content, &entry.data))
+            }
+            EntryKind::RefDelta => {
+                let base_hash = entry.base_hash.expect("ref-delta missing base hash");
+                let (base_kind, base_content) = match by_hash.get(&*base_hash) {
+                    Some((kind, content)) => (kind.clone(), content.clone()),
+                    None => {
+                        let base = GitObject::from(base_hash);
+                        (base.kind().clone(), base.content().to_vec())
+                    }
+                };
+                (base_kind, apply_delta(&base_content, &entry.data))
+            }
+        };
+
+        let object = GitObject::new(kind, content);
+        object.write();
+
+        by_offset.insert(
+            entry.offset,
+            (object.kind().clone(), object.content().to_vec()),
+        );
+        by_hash.insert(
+            object.hash().to_string(),
+            (object.kind().clone(), object.content().to_vec()),
+        );
+        result.push(object);
+    }
+
+    result
+}
+
+/// Reads the variable-length `(type, size)` entry header starting at `offset`.
+///
+/// The first byte's bits 6-4 hold the type and bits 3-0 the low size bits; if
+/// its MSB is set, each following byte contributes 7 more size bits (MSB-as-
+/// continuation), least-significant group first.
+fn read_entry_header(bytes: &[u8], offset: usize) -> (EntryKind, usize, usize) {
+    let first = bytes[offset];
+    let kind = EntryKind::from_type_bits((first >> 4) & 0x7);
+
+    let mut size = (first & 0x0f) as usize;
+    let mut shift = 4;
+    let mut i = offset + 1;
+    let mut more = first & 0x80 != 0;
+    while more {
+        let byte = bytes[i];
+        size |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+        more = byte & 0x80 != 0;
+        i += 1;
+    }
+
+    (kind, size, i - offset)
+}
+
+#[allow(dead_code)]
+fn write_entry_header(out: &mut Vec<u8>, type_bits: u8, size: usize) {
+    let mut remaining = size >> 4;
+    out.push((type_bits << 4) | (size as u8 & 0x0f) | if remaining > 0 { 0x80 } else { 0 });
+    while remaining > 0 {
+        let mut byte = (remaining & 0x7f) as u8;
+        remaining >>= 7;
+        if remaining > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+    }
+}
+
+/// Reads an ofs-delta negative base offset: a big-endian varint where each
+/// continuation byte adds 1 before shifting in the next 7 bits.
+fn read_ofs_delta_offset(bytes: &[u8], start: usize) -> (usize, usize) {
+    let mut i = start;
+    let mut byte = bytes[i];
+    let mut value = (byte & 0x7f) as usize;
+    i += 1;
+    while byte & 0x80 != 0 {
+        byte = bytes[i];
+        value += 1;
+        value = (value << 7) | (byte & 0x7f) as usize;
+        i += 1;
+    }
+    (value, i - start)
+}
+
+/// Inflates the zlib stream starting at `offset`, returning the decompressed
+/// bytes and the number of compressed bytes consumed.
+fn inflate_at(bytes: &[u8], offset: usize, expected_size: usize) -> (Vec<u8>, usize) {
+    let mut decoder = ZlibDecoder::new(&bytes[offset..]);
+    let mut out = Vec::with_capacity(expected_size);
+    decoder
+        .read_to_end(&mut out)
+        .expect("failed to inflate packfile entry");
+    assert_eq!(out.len(), expected_size, "inflated size mismatch");
+    (out, decoder.total_in() as usize)
+}
+
+fn verify_trailer(bytes: &[u8]) {
+    let (body, trailer) = bytes.split_at(bytes.len() - 20);
+    let mut hasher = Sha1::new();
+    hasher.update(body);
+    assert_eq!(&hasher.finalize()[..], trailer, "packfile checksum mismatch");
+}
+
+/// Applies a delta (`(base_size, result_size)` varints followed by copy/insert
+/// instructions) to `base`, producing the resolved object content.
+fn apply_delta(base: &[u8], delta: &[u8]) -> Vec<u8> {
+    let mut pos = 0;
+    let (base_size, consumed) = read_delta_varint(delta, pos);
+    pos += consumed;
+    assert_eq!(base_size, base.len(), "delta base size mismatch");
+    let (result_size, consumed) = read_delta_varint(delta, pos);
+    pos += consumed;
+
+    let mut result = Vec::with_capacity(result_size);
+    while pos < delta.len() {
+        let opcode = delta[pos];
+        pos += 1;
+        if opcode & 0x80 != 0 {
+            let (mut offset, mut size) = (0usize, 0usize);
+            for i in 0..4 {
+                if opcode & (1 << i) != 0 {
+                    offset |= (delta[pos] as usize) << (8 * i);
+                    pos += 1;
+                }
+            }
+            for i in 0..3 {
+                if opcode & (1 << (4 + i)) != 0 {
+                    size |= (delta[pos] as usize) << (8 * i);
+                    pos += 1;
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            result.extend_from_slice(&base[offset..offset + size]);
+        } else {
+            let size = opcode as usize;
+            result.extend_from_slice(&delta[pos..pos + size]);
+            pos += size;
+        }
+    }
+
+    assert_eq!(result.len(), result_size, "delta result size mismatch");
+    result
+}
+
+/// Reads a little-endian, MSB-continuation varint as used for delta `base_size`/`result_size`.
+fn read_delta_varint(data: &[u8], start: usize) -> (usize, usize) {
+    let mut size = 0usize;
+    let mut shift = 0;
+    let mut i = start;
+    loop {
+        let byte = data[i];
+        size |= ((byte & 0x7f) as usize) << shift;
+        i += 1;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    (size, i - start)
+}
@@ -0,0 +1,131 @@
+//! Git smart-HTTP transport: ref discovery and packfile negotiation.
+//!
+//! Speaks the pkt-line-framed `git-upload-pack` protocol over plain HTTP:
+//! GET `info/refs` to discover what the remote has, then POST a `want`/`done`
+//! negotiation and feed the resulting packfile to the `packfile` module.
+
+use std::fs;
+use std::io::Read;
+
+use crate::{packfile, Sha1Hash};
+
+/// Clones `url`, writing every fetched object as a loose object under
+/// `.git/objects`, and returns the hash the remote's HEAD points at. If the
+/// remote does not advertise HEAD, the hash of `refs/heads/main` is used
+/// instead, or else that of the first advertised ref.
+pub(crate) fn clone(url: &str) -> Sha1Hash {
+    let refs = discover_refs(url);
+    let head = refs
+        .iter()
+        .find(|(name, _)| name == "HEAD")
+        .or_else(|| refs.iter().find(|(name, _)| name == "refs/heads/main"))
+        .or_else(|| refs.first())
+        .map(|(_, hash)| hash.clone())
+        .expect("remote advertised no refs");
+
+    let pack_bytes = fetch_pack(url, &head);
+    let pack_path = std::env::temp_dir().join(format!("{head}.pack"));
+    fs::write(&pack_path, &pack_bytes).expect("could not write packfile to disk");
+    packfile::unpack(&pack_path);
+    let _ = fs::remove_file(&pack_path);
+
+    head
+}
+
+/// GETs `<url>/info/refs?service=git-upload-pack` and parses the `<sha> <name>` lines
+/// out of the pkt-line-framed ref advertisement.
+fn discover_refs(url: &str) -> Vec<(String, Sha1Hash)> {
+    let response = ureq::get(&format!("{url}/info/refs"))
+        .query("service", "git-upload-pack")
+        .call()
+        .expect("failed to fetch info/refs");
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut body)
+        .expect("failed to read info/refs response");
+
+    let mut refs = Vec::new();
+    for packet in pkt_line::split(&body) {
+        if packet.starts_with(b"#") {
+            continue; // service announcement, e.g. "# service=git-upload-pack"
+        }
+        let line = String::from_utf8_lossy(packet);
+        let line = line.trim_end_matches('\n');
+        // The first ref line additionally carries a NUL-separated capability list.
+        let line = line.split('\0').next().unwrap_or(line);
+        let Some((hash, name)) = line.split_once(' ') else {
+            continue;
+        };
+        if hash.len() != 40 {
+            continue;
+        }
+        refs.push((name.to_string(), Sha1Hash::from_hex(hash)));
+    }
+    refs
+}
+
+/// POSTs a `want <sha>` / `done` negotiation to `<url>/git-upload-pack` and
+/// returns the raw packfile bytes that follow the server's ack pkt-line.
+fn fetch_pack(url: &str, want: &Sha1Hash) -> Vec<u8> {
+    let mut request = pkt_line::encode(format!("want {want}\n").as_bytes());
+    request.extend_from_slice(pkt_line::FLUSH);
+    request.extend(pkt_line::encode(b"done\n"));
+
+    let response = ureq::post(&format!("{url}/git-upload-pack"))
+        .set("Content-Type", "application/x-git-upload-pack-request")
+        .send_bytes(&request)
+        .expect("git-upload-pack request failed");
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut body)
+        .expect("failed to read git-upload-pack response");
+
+    // The server replies with one ack/NAK pkt-line, then the raw packfile follows unframed.
+    let (_, consumed) = pkt_line::read_one(&body, 0);
+    body[consumed..].to_vec()
+}
+
+/// The pkt-line wire framing shared by ref discovery and the upload-pack negotiation.
+mod pkt_line {
+    pub(super) const FLUSH: &[u8] = b"0000";
+
+    /// Encodes `payload` as a single pkt-line: a 4-byte hex length prefix
+    /// (counting itself) followed by the payload.
+    pub(super) fn encode(payload: &[u8]) -> Vec<u8> {
+        let mut out = format!("{:04x}", payload.len() + 4).into_bytes();
+        out.extend_from_slice(payload);
+        out
+    }
+
+    /// Reads the pkt-line at `data[pos..]`, returning its payload (`None` for a
+    /// flush `0000` or delimiter `0001` packet) and the bytes consumed.
+    pub(super) fn read_one(data: &[u8], pos: usize) -> (Option<&[u8]>, usize) {
+        let len = usize::from_str_radix(
+            std::str::from_utf8(&data[pos..pos + 4]).expect("invalid pkt-line length"),
+            16,
+        )
+        .expect("invalid pkt-line length");
+        if len == 0 || len == 1 {
+            (None, 4)
+        } else {
+            (Some(&data[pos + 4..pos + len]), len)
+        }
+    }
+
+    /// Splits a buffer of concatenated pkt-lines into payloads, dropping
+    /// flush/delimiter packets.
+    pub(super) fn split(data: &[u8]) -> Vec<&[u8]> {
+        let mut packets = Vec::new();
+        let mut pos = 0;
+        while pos + 4 <= data.len() {
+            let (packet, consumed) = read_one(data, pos);
+            if let Some(packet) = packet {
+                packets.push(packet);
+            }
+            pos += consumed;
+        }
+        packets
+    }
+}